@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 /// Events we care about from cargo test JSON output
 #[derive(Debug, Clone)]
@@ -9,13 +9,11 @@ pub enum TestEvent {
     /// A test passed
     Passed {
         name: String,
-        #[allow(dead_code)]
         duration_secs: Option<f64>,
     },
     /// A test failed
     Failed {
         name: String,
-        #[allow(dead_code)]
         duration_secs: Option<f64>,
         stdout: Option<String>,
         stderr: Option<String>,
@@ -25,9 +23,47 @@ pub enum TestEvent {
     /// A test timed out
     Timeout {
         name: String,
-        #[allow(dead_code)]
         duration_secs: Option<f64>,
     },
+    /// The test suite has finished running
+    SuiteFinished {
+        passed: usize,
+        failed: usize,
+        ignored: usize,
+        measured: usize,
+        filtered_out: usize,
+    },
+    /// A benchmark has finished running
+    Benchmark {
+        name: String,
+        median: u64,
+        deviation: u64,
+    },
+}
+
+/// Deserialize `exec_time` as either a JSON number or a string such as
+/// `"0.000s"`, which is what libtest's `--report-time` JSON emits on some
+/// rustc versions.
+fn deserialize_exec_time<'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ExecTime {
+        Number(f64),
+        String(String),
+    }
+
+    match Option::<ExecTime>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(ExecTime::Number(n)) => Ok(Some(n)),
+        Some(ExecTime::String(s)) => s
+            .trim_end_matches('s')
+            .parse()
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
 }
 
 /// Top-level JSON event from cargo test
@@ -35,22 +71,36 @@ pub enum TestEvent {
 #[serde(tag = "type", rename_all = "lowercase")]
 enum JsonEvent {
     Suite {
-        #[allow(dead_code)]
         event: String,
         #[serde(default)]
         #[allow(dead_code)]
         test_count: Option<usize>,
+        #[serde(default)]
+        passed: usize,
+        #[serde(default)]
+        failed: usize,
+        #[serde(default)]
+        ignored: usize,
+        #[serde(default)]
+        measured: usize,
+        #[serde(default)]
+        filtered_out: usize,
     },
     Test {
         event: String,
         name: String,
-        #[serde(default)]
+        #[serde(default, deserialize_with = "deserialize_exec_time")]
         exec_time: Option<f64>,
         #[serde(default)]
         stdout: Option<String>,
         #[serde(default)]
         stderr: Option<String>,
     },
+    Bench {
+        name: String,
+        median: u64,
+        deviation: u64,
+    },
 }
 
 /// Parse a JSON line from cargo test output
@@ -58,10 +108,25 @@ pub fn parse_event(line: &str) -> Result<Option<TestEvent>> {
     let json_event: JsonEvent = serde_json::from_str(line).context("Failed to parse JSON event")?;
 
     match json_event {
-        JsonEvent::Suite { .. } => {
-            // We don't emit events for suite start/end
-            Ok(None)
-        }
+        JsonEvent::Suite {
+            event,
+            passed,
+            failed,
+            ignored,
+            measured,
+            filtered_out,
+            ..
+        } => match event.as_str() {
+            "ok" | "failed" => Ok(Some(TestEvent::SuiteFinished {
+                passed,
+                failed,
+                ignored,
+                measured,
+                filtered_out,
+            })),
+            // We don't emit events for suite start
+            _ => Ok(None),
+        },
         JsonEvent::Test {
             event,
             name,
@@ -93,6 +158,15 @@ pub fn parse_event(line: &str) -> Result<Option<TestEvent>> {
             };
             Ok(Some(test_event))
         }
+        JsonEvent::Bench {
+            name,
+            median,
+            deviation,
+        } => Ok(Some(TestEvent::Benchmark {
+            name,
+            median,
+            deviation,
+        })),
     }
 }
 
@@ -162,4 +236,56 @@ mod tests {
             _ => panic!("Expected Ignored event"),
         }
     }
+
+    #[test]
+    fn test_parse_exec_time_as_string() {
+        let line = r#"{"type":"test","event":"ok","name":"my_test","exec_time":"0.000s"}"#;
+        let event = parse_event(line).unwrap().unwrap();
+        match event {
+            TestEvent::Passed { duration_secs, .. } => {
+                assert_eq!(duration_secs, Some(0.0));
+            }
+            _ => panic!("Expected Passed event"),
+        }
+    }
+
+    #[test]
+    fn test_parse_suite_finished() {
+        let line = r#"{"type":"suite","event":"ok","passed":2,"failed":0,"ignored":1,"measured":0,"filtered_out":3}"#;
+        let event = parse_event(line).unwrap().unwrap();
+        match event {
+            TestEvent::SuiteFinished {
+                passed,
+                failed,
+                ignored,
+                measured,
+                filtered_out,
+            } => {
+                assert_eq!(passed, 2);
+                assert_eq!(failed, 0);
+                assert_eq!(ignored, 1);
+                assert_eq!(measured, 0);
+                assert_eq!(filtered_out, 3);
+            }
+            _ => panic!("Expected SuiteFinished event"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let line = r#"{"type":"bench","name":"my_bench","median":1234,"deviation":56}"#;
+        let event = parse_event(line).unwrap().unwrap();
+        match event {
+            TestEvent::Benchmark {
+                name,
+                median,
+                deviation,
+            } => {
+                assert_eq!(name, "my_bench");
+                assert_eq!(median, 1234);
+                assert_eq!(deviation, 56);
+            }
+            _ => panic!("Expected Benchmark event"),
+        }
+    }
 }
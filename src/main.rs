@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
 use clap::Parser;
+use rand::Rng;
 use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 
 mod json_parser;
 mod subunit_writer;
+mod watch;
 
 use subunit_writer::SubunitWriter;
 
@@ -23,6 +25,24 @@ struct Cli {
     #[command(flatten)]
     mode: Mode,
 
+    /// Watch the crate's source tree and re-run tests on change
+    #[arg(long, conflicts_with_all = ["list", "shuffle"])]
+    watch: bool,
+
+    /// Restrict the paths watched by --watch (defaults to `src` and `Cargo.toml`)
+    #[arg(long, value_name = "PATH", requires = "watch")]
+    watch_path: Vec<String>,
+
+    /// Shuffle test order, optionally with a reproducible seed (--shuffle=SEED)
+    #[arg(
+        long,
+        value_name = "SEED",
+        num_args = 0..=1,
+        require_equals = true,
+        conflicts_with_all = ["list", "load_list", "watch"]
+    )]
+    shuffle: Option<Option<u64>>,
+
     /// Additional arguments to pass to cargo test
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     cargo_args: Vec<String>,
@@ -32,18 +52,26 @@ struct Cli {
 #[group(multiple = false)]
 struct Mode {
     /// List all available tests without running them
-    #[arg(long)]
+    #[arg(long, conflicts_with_all = ["watch", "shuffle"])]
     list: bool,
 
     /// Load test names from a file (one per line) and run only those tests
-    #[arg(long, value_name = "FILE")]
+    #[arg(long, value_name = "FILE", conflicts_with = "shuffle")]
     load_list: Option<String>,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    if cli.mode.list {
+    if cli.watch {
+        let test_filters = match &cli.mode.load_list {
+            Some(load_list_file) => read_test_names(load_list_file)?,
+            None => Vec::new(),
+        };
+        watch::watch(&cli.watch_path, &test_filters, &cli.cargo_args)
+    } else if let Some(seed) = cli.shuffle {
+        run_tests_shuffled(seed, &cli.cargo_args)
+    } else if cli.mode.list {
         list_tests(&cli.cargo_args)
     } else if let Some(load_list_file) = &cli.mode.load_list {
         run_tests_from_file(load_list_file, &cli.cargo_args)
@@ -52,8 +80,8 @@ fn main() -> Result<()> {
     }
 }
 
-/// List all available tests
-fn list_tests(cargo_args: &[String]) -> Result<()> {
+/// Enumerate available test names via `cargo test --list`
+fn list_test_names(cargo_args: &[String]) -> Result<Vec<String>> {
     let mut cmd = Command::new("cargo");
     cmd.arg("test");
     cmd.args(cargo_args);
@@ -68,8 +96,8 @@ fn list_tests(cargo_args: &[String]) -> Result<()> {
         );
     }
 
-    // Parse and print test names
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut names = Vec::new();
     for line in stdout.lines() {
         let line = line.trim();
         // Skip empty lines and the summary line
@@ -78,20 +106,46 @@ fn list_tests(cargo_args: &[String]) -> Result<()> {
         }
         // Remove ": test" or ": bench" suffix
         if let Some(test_name) = line.strip_suffix(": test") {
-            println!("{}", test_name);
+            names.push(test_name.to_string());
         } else if let Some(test_name) = line.strip_suffix(": bench") {
-            println!("{}", test_name);
+            names.push(test_name.to_string());
         } else {
-            // Fallback: print as-is
-            println!("{}", line);
+            // Fallback: treat as-is
+            names.push(line.to_string());
         }
     }
 
+    Ok(names)
+}
+
+/// List all available tests
+fn list_tests(cargo_args: &[String]) -> Result<()> {
+    for name in list_test_names(cargo_args)? {
+        println!("{}", name);
+    }
+
     Ok(())
 }
 
-/// Run tests specified in a file
-fn run_tests_from_file(file_path: &str, cargo_args: &[String]) -> Result<()> {
+/// Build the libtest flags that make it shuffle its own run order with the given seed.
+///
+/// libtest (not this binary) owns execution order, so reproducible shuffling
+/// has to go through its native `--shuffle`/`--shuffle-seed` unstable flags
+/// rather than us reordering the filter arguments we pass it.
+fn shuffle_flags(seed: u64) -> Vec<String> {
+    vec!["--shuffle".to_string(), format!("--shuffle-seed={}", seed)]
+}
+
+/// Run tests in a shuffled order, printing the seed used so a failing run can be replayed
+fn run_tests_shuffled(seed: Option<u64>, cargo_args: &[String]) -> Result<()> {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    eprintln!("Shuffling tests with seed: {}", seed);
+
+    run_tests_with_flags(&[], cargo_args, &shuffle_flags(seed))
+}
+
+/// Read test names (one per line) from a file
+fn read_test_names(file_path: &str) -> Result<Vec<String>> {
     let file = std::fs::File::open(file_path)
         .context(format!("Failed to open test list file: {}", file_path))?;
     let reader = BufReader::new(file);
@@ -109,11 +163,27 @@ fn run_tests_from_file(file_path: &str, cargo_args: &[String]) -> Result<()> {
         anyhow::bail!("No test names found in file: {}", file_path);
     }
 
+    Ok(test_names)
+}
+
+/// Run tests specified in a file
+fn run_tests_from_file(file_path: &str, cargo_args: &[String]) -> Result<()> {
+    let test_names = read_test_names(file_path)?;
     run_tests_with_filters(&test_names, cargo_args)
 }
 
 /// Run tests with optional test name filters
-fn run_tests_with_filters(test_filters: &[String], cargo_args: &[String]) -> Result<()> {
+pub(crate) fn run_tests_with_filters(test_filters: &[String], cargo_args: &[String]) -> Result<()> {
+    run_tests_with_flags(test_filters, cargo_args, &[])
+}
+
+/// Run tests with optional test name filters and extra unstable libtest flags
+/// (e.g. `--shuffle-seed=<seed>`)
+fn run_tests_with_flags(
+    test_filters: &[String],
+    cargo_args: &[String],
+    extra_flags: &[String],
+) -> Result<()> {
     let mut cmd = Command::new("cargo");
     cmd.arg("test");
     cmd.args(cargo_args);
@@ -127,6 +197,7 @@ fn run_tests_with_filters(test_filters: &[String], cargo_args: &[String]) -> Res
         "json",
         "--report-time",
     ]);
+    cmd.args(extra_flags);
 
     // Add test filters
     for filter in test_filters {
@@ -181,3 +252,22 @@ fn run_tests_with_filters(test_filters: &[String], cargo_args: &[String]) -> Res
 fn run_tests(cargo_args: &[String]) -> Result<()> {
     run_tests_with_filters(&[], cargo_args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffle_flags_are_deterministic_for_a_seed() {
+        assert_eq!(shuffle_flags(42), shuffle_flags(42));
+        assert_eq!(
+            shuffle_flags(42),
+            vec!["--shuffle".to_string(), "--shuffle-seed=42".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_shuffle_flags_differ_by_seed() {
+        assert_ne!(shuffle_flags(1), shuffle_flags(2));
+    }
+}
@@ -1,46 +1,107 @@
-use anyhow::Result;
-use chrono::Utc;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::io::Write;
 use subunit::Event;
 
 use crate::json_parser::TestEvent;
 
+/// JSON payload attached to a benchmark's `measurement` file.
+#[derive(Serialize)]
+struct Measurement {
+    median: u64,
+    deviation: u64,
+}
+
 /// Writer that converts test events to subunit format
 pub struct SubunitWriter<W: Write> {
     output: W,
+    /// Wall-clock instant each test was seen to start, keyed by test name.
+    ///
+    /// cargo runs tests in parallel and interleaves their JSON events, so we
+    /// can't just stamp events as they arrive: we hold the start time here
+    /// until the matching result event tells us how long the test actually
+    /// took.
+    start_times: HashMap<String, DateTime<Utc>>,
 }
 
 impl<W: Write> SubunitWriter<W> {
     /// Create a new subunit writer
     pub fn new(output: W) -> Self {
-        Self { output }
+        Self {
+            output,
+            start_times: HashMap::new(),
+        }
+    }
+
+    /// Resolve the start/end timestamps for a finished test.
+    ///
+    /// Uses the recorded `started` time if we saw one, otherwise falls back
+    /// to `now - duration` so the two timestamps still span the reported
+    /// `exec_time` even when the `started` event was missed.
+    fn test_times(&mut self, name: &str, duration_secs: Option<f64>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let now = Utc::now();
+        let duration = duration_secs.map(|secs| Duration::milliseconds((secs * 1000.0) as i64));
+
+        let start = self.start_times.remove(name).unwrap_or_else(|| match duration {
+            Some(d) => now - d,
+            None => now,
+        });
+        let end = match duration {
+            Some(d) => start + d,
+            None => now,
+        };
+
+        (start, end)
+    }
+
+    /// Write an `inprogress` packet for a test, stamped with its start time.
+    fn write_inprogress(&mut self, name: &str, timestamp: DateTime<Utc>) -> Result<()> {
+        let mut evt = Event {
+            status: Some("inprogress".to_string()),
+            test_id: Some(name.to_string()),
+            timestamp: Some(timestamp),
+            file_name: None,
+            file_content: None,
+            mime_type: None,
+            route_code: None,
+            tags: None,
+        };
+        evt.write(&mut self.output)
+            .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))
+    }
+
+    /// Write a status-less packet attaching a single named file to a test_id.
+    fn write_attachment(&mut self, name: &str, file_name: &str, content: &[u8]) -> Result<()> {
+        let mut evt = Event {
+            status: None,
+            test_id: Some(name.to_string()),
+            timestamp: Some(Utc::now()),
+            file_name: Some(file_name.to_string()),
+            file_content: Some(content.to_vec()),
+            mime_type: Some("text/plain;charset=utf8".to_string()),
+            route_code: None,
+            tags: None,
+        };
+        evt.write(&mut self.output)
+            .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))
     }
 
     /// Write a test event in subunit format
     pub fn write_event(&mut self, event: &TestEvent) -> Result<()> {
         match event {
             TestEvent::Started { name } => {
-                let mut evt = Event {
-                    status: Some("inprogress".to_string()),
-                    test_id: Some(name.clone()),
-                    timestamp: Some(Utc::now()),
-                    file_name: None,
-                    file_content: None,
-                    mime_type: None,
-                    route_code: None,
-                    tags: None,
-                };
-                evt.write(&mut self.output)
-                    .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
+                self.start_times.insert(name.clone(), Utc::now());
             }
-            TestEvent::Passed {
-                name,
-                duration_secs: _,
-            } => {
+            TestEvent::Passed { name, duration_secs } => {
+                let (start, end) = self.test_times(name, *duration_secs);
+                self.write_inprogress(name, start)?;
+
                 let mut evt = Event {
                     status: Some("success".to_string()),
                     test_id: Some(name.clone()),
-                    timestamp: Some(Utc::now()),
+                    timestamp: Some(end),
                     file_name: None,
                     file_content: None,
                     mime_type: None,
@@ -52,43 +113,41 @@ impl<W: Write> SubunitWriter<W> {
             }
             TestEvent::Failed {
                 name,
-                duration_secs: _,
+                duration_secs,
                 stdout,
                 stderr,
             } => {
-                // First write the failure event
+                let (start, end) = self.test_times(name, *duration_secs);
+                self.write_inprogress(name, start)?;
+
+                // The fail status packet itself carries no attachment; subunit
+                // v2 allows further packets against the same test_id, so
+                // stdout and stderr each get their own status-less packet
+                // instead of one clobbering the other.
                 let mut evt = Event {
                     status: Some("fail".to_string()),
                     test_id: Some(name.clone()),
-                    timestamp: Some(Utc::now()),
+                    timestamp: Some(end),
                     file_name: None,
                     file_content: None,
                     mime_type: None,
                     route_code: None,
                     tags: None,
                 };
+                evt.write(&mut self.output)
+                    .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
 
-                // Attach stdout if present
                 if let Some(stdout_content) = stdout {
                     if !stdout_content.is_empty() {
-                        evt.file_name = Some("stdout".to_string());
-                        evt.file_content = Some(stdout_content.as_bytes().to_vec());
-                        evt.mime_type = Some("text/plain;charset=utf8".to_string());
+                        self.write_attachment(name, "stdout", stdout_content.as_bytes())?;
                     }
                 }
 
-                // Note: subunit v2 allows only one file attachment per event
-                // If both stdout and stderr exist, we prefer stderr (more important for failures)
                 if let Some(stderr_content) = stderr {
                     if !stderr_content.is_empty() {
-                        evt.file_name = Some("stderr".to_string());
-                        evt.file_content = Some(stderr_content.as_bytes().to_vec());
-                        evt.mime_type = Some("text/plain;charset=utf8".to_string());
+                        self.write_attachment(name, "stderr", stderr_content.as_bytes())?;
                     }
                 }
-
-                evt.write(&mut self.output)
-                    .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
             }
             TestEvent::Ignored { name } => {
                 let mut evt = Event {
@@ -104,15 +163,15 @@ impl<W: Write> SubunitWriter<W> {
                 evt.write(&mut self.output)
                     .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
             }
-            TestEvent::Timeout {
-                name,
-                duration_secs: _,
-            } => {
+            TestEvent::Timeout { name, duration_secs } => {
+                let (start, end) = self.test_times(name, *duration_secs);
+                self.write_inprogress(name, start)?;
+
                 // Treat timeout as a failure
                 let mut evt = Event {
                     status: Some("fail".to_string()),
                     test_id: Some(name.clone()),
-                    timestamp: Some(Utc::now()),
+                    timestamp: Some(end),
                     file_name: Some("reason".to_string()),
                     file_content: Some(b"Test timed out".to_vec()),
                     mime_type: Some("text/plain;charset=utf8".to_string()),
@@ -122,6 +181,59 @@ impl<W: Write> SubunitWriter<W> {
                 evt.write(&mut self.output)
                     .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
             }
+            TestEvent::SuiteFinished {
+                passed,
+                failed,
+                ignored,
+                measured,
+                filtered_out,
+            } => {
+                // A suite-level summary packet with no test_id, tagged with
+                // the totals so aggregators can validate they received
+                // every result.
+                let mut evt = Event {
+                    status: Some("success".to_string()),
+                    test_id: None,
+                    timestamp: Some(Utc::now()),
+                    file_name: None,
+                    file_content: None,
+                    mime_type: None,
+                    route_code: None,
+                    tags: Some(vec![
+                        format!("passed:{}", passed),
+                        format!("failed:{}", failed),
+                        format!("ignored:{}", ignored),
+                        format!("measured:{}", measured),
+                        format!("filtered_out:{}", filtered_out),
+                    ]),
+                };
+                evt.write(&mut self.output)
+                    .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
+            }
+            TestEvent::Benchmark {
+                name,
+                median,
+                deviation,
+            } => {
+                let measurement = serde_json::to_string(&Measurement {
+                    median: *median,
+                    deviation: *deviation,
+                })
+                .context("Failed to serialize benchmark measurement")?;
+
+                let mut evt = Event {
+                    status: Some("success".to_string()),
+                    test_id: Some(name.clone()),
+                    timestamp: Some(Utc::now()),
+                    file_name: Some("measurement".to_string()),
+                    file_content: Some(measurement.into_bytes()),
+                    mime_type: Some("application/json".to_string()),
+                    route_code: None,
+                    tags: Some(vec!["benchmark".to_string()]),
+                };
+                evt.write(&mut self.output)
+                    .map_err(|e| anyhow::anyhow!("Failed to write subunit event: {}", e))?;
+            }
         }
 
         // Flush after each event to ensure real-time output
@@ -146,8 +258,10 @@ mod tests {
             })
             .unwrap();
 
-        // Just verify that something was written
-        assert!(!output.is_empty());
+        // A started test only records its start time; the inprogress packet
+        // isn't emitted until the matching result arrives.
+        assert!(output.is_empty());
+        assert!(writer.start_times.contains_key("my_test"));
     }
 
     #[test]
@@ -179,7 +293,43 @@ mod tests {
             })
             .unwrap();
 
-        assert!(!output.is_empty());
+        // Both stdout and stderr must survive as separate attachments, not
+        // have one clobber the other.
+        assert!(contains_bytes(&output, b"stdout"));
+        assert!(contains_bytes(&output, b"stderr"));
+        assert!(contains_bytes(&output, b"test output"));
+        assert!(contains_bytes(&output, b"error message"));
+
+        // inprogress, fail, stdout attachment, stderr attachment
+        assert_eq!(packet_count(&output), 4);
+    }
+
+    /// Check whether `needle` appears anywhere in `haystack`.
+    fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    /// Count subunit v2 packets by their 0xb3 signature byte.
+    ///
+    /// Walks packet by packet using each packet's length field, so a 0xb3
+    /// byte inside a CRC, timestamp or attachment isn't miscounted.
+    fn packet_count(output: &[u8]) -> usize {
+        let mut count = 0;
+        let mut pos = 0;
+        while pos < output.len() {
+            assert_eq!(output[pos], 0xb3, "no packet signature at byte {}", pos);
+            // Length follows signature and 2 flag bytes; the top 2 bits of its
+            // first byte give the number of extra big-endian bytes.
+            let first = output[pos + 3];
+            let extra = (first >> 6) as usize;
+            let mut length = (first & 0x3f) as usize;
+            for &b in &output[pos + 4..pos + 4 + extra] {
+                length = (length << 8) | b as usize;
+            }
+            pos += length;
+            count += 1;
+        }
+        count
     }
 
     #[test]
@@ -195,4 +345,71 @@ mod tests {
 
         assert!(!output.is_empty());
     }
+
+    #[test]
+    fn test_write_suite_finished_event() {
+        let mut output = Vec::new();
+        let mut writer = SubunitWriter::new(&mut output);
+
+        writer
+            .write_event(&TestEvent::SuiteFinished {
+                passed: 2,
+                failed: 0,
+                ignored: 1,
+                measured: 0,
+                filtered_out: 3,
+            })
+            .unwrap();
+
+        assert!(!output.is_empty());
+    }
+
+    #[test]
+    fn test_write_benchmark_event() {
+        let mut output = Vec::new();
+        let mut writer = SubunitWriter::new(&mut output);
+
+        writer
+            .write_event(&TestEvent::Benchmark {
+                name: "my_bench".to_string(),
+                median: 1234,
+                deviation: 56,
+            })
+            .unwrap();
+
+        assert!(!output.is_empty());
+    }
+
+    #[test]
+    fn test_test_times_uses_recorded_start() {
+        let mut output = Vec::new();
+        let mut writer = SubunitWriter::new(&mut output);
+
+        let recorded_start = Utc::now() - Duration::seconds(5);
+        writer
+            .start_times
+            .insert("my_test".to_string(), recorded_start);
+
+        let (start, end) = writer.test_times("my_test", Some(2.0));
+
+        assert_eq!(start, recorded_start);
+        assert_eq!(end, recorded_start + Duration::milliseconds(2000));
+        // The entry should be consumed so a later call doesn't reuse it.
+        assert!(!writer.start_times.contains_key("my_test"));
+    }
+
+    #[test]
+    fn test_test_times_falls_back_when_start_was_missed() {
+        let mut output = Vec::new();
+        let mut writer = SubunitWriter::new(&mut output);
+
+        let before = Utc::now();
+        let (start, end) = writer.test_times("my_test", Some(2.0));
+        let after = Utc::now();
+
+        // No recorded start, so it's reconstructed as `now - duration`.
+        assert_eq!(end - start, Duration::milliseconds(2000));
+        assert!(start >= before - Duration::milliseconds(2000));
+        assert!(end <= after);
+    }
 }
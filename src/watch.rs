@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use crate::run_tests_with_filters;
+
+/// How long to wait after a change before re-running, so a burst of saves
+/// (e.g. a editor writing several files at once) only triggers one run.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watch the crate's source tree and re-run `run_tests_with_filters`
+/// whenever a `.rs` file or `Cargo.toml` changes.
+///
+/// Each run emits a complete, self-contained subunit v2 stream to stdout,
+/// so a long-lived consumer can treat successive runs as distinct result
+/// sets.
+pub fn watch(watch_paths: &[String], test_filters: &[String], cargo_args: &[String]) -> Result<()> {
+    let paths: Vec<PathBuf> = if watch_paths.is_empty() {
+        vec![PathBuf::from("src"), PathBuf::from("Cargo.toml")]
+    } else {
+        watch_paths.iter().map(PathBuf::from).collect()
+    };
+
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })
+    .context("Failed to create file watcher")?;
+
+    for path in &paths {
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", path.display()))?;
+    }
+
+    eprintln!("Watching for changes in {:?}... (Ctrl+C to stop)", paths);
+
+    loop {
+        let event: notify::Event = match rx.recv() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+
+        if !is_relevant(&event) {
+            continue;
+        }
+
+        // Drain any further events that arrive within the debounce window.
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        eprintln!("Change detected, re-running tests...");
+        if let Err(e) = run_tests_with_filters(test_filters, cargo_args) {
+            eprintln!("Error running tests: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Only `.rs` and `Cargo.toml` changes should trigger a re-run.
+fn is_relevant(event: &notify::Event) -> bool {
+    event.paths.iter().any(|p| is_watched_file(p))
+}
+
+fn is_watched_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "rs")
+        || path.file_name().is_some_and(|name| name == "Cargo.toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_watched_file_rust_source() {
+        assert!(is_watched_file(Path::new("foo.rs")));
+        assert!(is_watched_file(Path::new("src/watch.rs")));
+    }
+
+    #[test]
+    fn test_is_watched_file_cargo_toml() {
+        assert!(is_watched_file(Path::new("Cargo.toml")));
+    }
+
+    #[test]
+    fn test_is_watched_file_ignores_other_files() {
+        assert!(!is_watched_file(Path::new("target/debug/foo")));
+        assert!(!is_watched_file(Path::new("README.md")));
+        assert!(!is_watched_file(Path::new("Cargo.lock")));
+    }
+}